@@ -1,22 +1,33 @@
 //! Manage PostgreSQL archive
 #![allow(dead_code)]
+use crate::cache;
 use crate::error::ArchiveError::{AssetHashNotFound, AssetNotFound, ReleaseNotFound, Unexpected};
 use crate::error::Result;
 use crate::github::{Asset, Release};
+use crate::retry;
 use crate::version::Version;
 use bytes::Bytes;
+use bzip2::bufread::BzDecoder;
 use flate2::bufread::GzDecoder;
 use regex::Regex;
 use reqwest::header::HeaderMap;
 use reqwest::{header, RequestBuilder};
 use std::fs::{create_dir_all, File};
-use std::io::{copy, BufReader, Cursor};
+use std::io::{copy, BufReader, Cursor, Read};
 use std::path::Path;
 use std::str::FromStr;
 use tar::Archive;
+use xz2::bufread::XzDecoder;
+use zip::ZipArchive;
+use zstd::stream::read::Decoder as ZstdDecoder;
+
+pub use crate::retry::{set_retry_config, RetryConfig};
 
 const GITHUB_API_VERSION_HEADER: &str = "X-GitHub-Api-Version";
 const GITHUB_API_VERSION: &str = "2022-11-28";
+/// Host that cached archives are namespaced under; used as the `<releases-host>` path segment
+/// described in [`cache`].
+const GITHUB_RELEASES_HOST: &str = "github.com";
 
 lazy_static! {
     static ref GITHUB_TOKEN: Option<String> = match std::env::var("GITHUB_TOKEN") {
@@ -68,7 +79,7 @@ async fn get_release(version: &Version) -> Result<Release> {
         let request = client
             .get(format!("{url}/tags/{version}"))
             .add_github_headers()?;
-        let response = request.send().await?.error_for_status()?;
+        let response = retry::send(request).await?.error_for_status()?;
         let release = response.json::<Release>().await?;
 
         return Ok(release);
@@ -82,7 +93,7 @@ async fn get_release(version: &Version) -> Result<Release> {
             .get(url)
             .add_github_headers()?
             .query(&[("page", page.to_string().as_str()), ("per_page", "100")]);
-        let response = request.send().await?.error_for_status()?;
+        let response = retry::send(request).await?.error_for_status()?;
         let response_releases = response.json::<Vec<Release>>().await?;
         if response_releases.is_empty() {
             break;
@@ -122,35 +133,55 @@ pub async fn get_version(version: &Version) -> Result<Version> {
     Version::from_str(&release.tag_name)
 }
 
+/// Archive filename extensions tried, in order, when resolving a release asset for a given
+/// version and target. Mirrors the formats recognized by [`extract`], so that mirrors which
+/// publish e.g. `.tar.zst` or `.zip` archives instead of `.tar.gz` can still be resolved.
+const ASSET_EXTENSIONS: [&str; 5] = ["tar.gz", "tar.xz", "tar.bz2", "tar.zst", "zip"];
+
 /// Gets the assets for a given [`version`](Version) of PostgreSQL and `target` (e.g. `x86_64-unknown-linux-gnu`).
 /// If the [`version`](Version) or `target` is not found, then an [error](crate::error::ArchiveError) is returned.
 ///
 /// Two assets are returned. The first [asset](Asset) is the archive, and the second [asset](Asset) is the archive hash.
 async fn get_asset<S: AsRef<str>>(version: &Version, target: S) -> Result<(Version, Asset, Asset)> {
-    let release = get_release(version).await?;
+    let mut release = get_release(version).await?;
     let asset_version = Version::from_str(&release.tag_name)?;
-    let mut asset: Option<Asset> = None;
-    let mut asset_hash: Option<Asset> = None;
-    let asset_name = format!("postgresql-{}-{}.tar.gz", asset_version, target.as_ref());
-    let asset_hash_name = format!("{asset_name}.sha256");
-
-    for release_asset in release.assets {
-        if release_asset.name == asset_name {
-            asset = Some(release_asset);
-        } else if release_asset.name == asset_hash_name {
-            asset_hash = Some(release_asset);
-        }
+    let target = target.as_ref();
 
-        if asset.is_some() && asset_hash.is_some() {
-            break;
-        }
-    }
+    for extension in ASSET_EXTENSIONS {
+        let asset_name = format!("postgresql-{asset_version}-{target}.{extension}");
+        let asset_hash_name = format!("{asset_name}.sha256");
+
+        let Some(asset_index) = release.assets.iter().position(|a| a.name == asset_name) else {
+            continue;
+        };
+        let Some(asset_hash_index) = release
+            .assets
+            .iter()
+            .position(|a| a.name == asset_hash_name)
+        else {
+            continue;
+        };
+
+        // Remove the higher index first so the lower index stays valid.
+        let (higher_index, lower_index, higher_is_asset) = if asset_index > asset_hash_index {
+            (asset_index, asset_hash_index, true)
+        } else {
+            (asset_hash_index, asset_index, false)
+        };
+        let higher = release.assets.remove(higher_index);
+        let lower = release.assets.remove(lower_index);
+        let (asset, asset_hash) = if higher_is_asset {
+            (higher, lower)
+        } else {
+            (lower, higher)
+        };
 
-    match (asset, asset_hash) {
-        (Some(asset), Some(asset_hash)) => Ok((asset_version, asset, asset_hash)),
-        (None, _) => Err(AssetNotFound(asset_name.to_string())),
-        (_, None) => Err(AssetNotFound(asset_name.to_string())),
+        return Ok((asset_version, asset, asset_hash));
     }
+
+    Err(AssetNotFound(format!(
+        "postgresql-{asset_version}-{target}.*"
+    )))
 }
 
 /// Gets the archive for a given [`version`](Version) of PostgreSQL for the current target.
@@ -164,17 +195,22 @@ pub async fn get_archive(version: &Version) -> Result<(Version, Bytes, String)>
 /// Gets the archive for a given [`version`](Version) of PostgreSQL and `target` (e.g. `x86_64-unknown-linux-gnu`).
 /// If the [`version`](Version) or `target` is not found, then an [error](crate::error::ArchiveError) is returned.
 ///
+/// Before downloading, the [`cache`] is checked for a previously downloaded archive whose
+/// SHA-256 matches the hash published alongside this release; if one is found, it is returned
+/// without making a network request for the archive itself.
+///
 /// Returns the archive bytes and the archive hash.
 pub async fn get_archive_for_target<S: AsRef<str>>(
     version: &Version,
     target: S,
 ) -> Result<(Version, Bytes, String)> {
+    let target = target.as_ref();
     let (asset_version, asset, asset_hash) = get_asset(version, target).await?;
     let client = reqwest::Client::new();
     let request = client
         .get(asset_hash.browser_download_url)
         .add_github_headers()?;
-    let response = request.send().await?.error_for_status()?;
+    let response = retry::send(request).await?.error_for_status()?;
     let text = response.text().await?;
     let re = Regex::new(r"[0-9a-f]{64}")?;
     let hash = match re.find(&text) {
@@ -182,18 +218,85 @@ pub async fn get_archive_for_target<S: AsRef<str>>(
         None => return Err(AssetHashNotFound(asset.name)),
     };
 
+    if let Some(archive) = cache::get(GITHUB_RELEASES_HOST, &asset_version, target, &hash) {
+        return Ok((asset_version, archive, hash));
+    }
+
     let asset_url = asset.browser_download_url;
     let request = client.get(asset_url).add_github_headers()?;
-    let response = request.send().await?.error_for_status()?;
+    let response = retry::send(request).await?.error_for_status()?;
     let archive: Bytes = response.bytes().await?;
 
+    // Caching is a best-effort optimization; a write failure should not fail the download.
+    let _ = cache::put(GITHUB_RELEASES_HOST, &asset_version, target, &archive);
+
     Ok((asset_version, archive, hash))
 }
 
-/// Extracts the compressed tar `bytes` to the `out_dir`.
+/// Removes all cached archives.
+///
+/// # Errors
+/// Returns an error if the cache directory exists but cannot be removed.
+pub fn clear_cache() -> Result<()> {
+    cache::clear_cache()
+}
+
+/// Removes the cached archive for the given [`version`](Version) and `target`.
+///
+/// # Errors
+/// Returns an error if a cached file exists but cannot be removed.
+pub fn clear_cache_for<S: AsRef<str>>(version: &Version, target: S) -> Result<()> {
+    cache::clear_cache_for(version, target.as_ref())
+}
+
+/// Archive formats recognized by [`extract`], detected from the magic bytes at the start of the
+/// archive rather than from a file extension.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ArchiveFormat {
+    TarGz,
+    TarXz,
+    TarBz2,
+    TarZst,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Detects the archive format from the magic bytes at the start of `bytes`.
+    fn detect(bytes: &[u8]) -> Result<Self> {
+        if bytes.starts_with(&[0x1F, 0x8B]) {
+            Ok(Self::TarGz)
+        } else if bytes.starts_with(&[0xFD, 0x37, 0x7A, 0x58, 0x5A, 0x00]) {
+            Ok(Self::TarXz)
+        } else if bytes.starts_with(&[0x42, 0x5A, 0x68]) {
+            Ok(Self::TarBz2)
+        } else if bytes.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]) {
+            Ok(Self::TarZst)
+        } else if bytes.starts_with(&[0x50, 0x4B, 0x03, 0x04]) {
+            Ok(Self::Zip)
+        } else {
+            Err(Unexpected("Unrecognized archive format".to_string()))
+        }
+    }
+}
+
+/// Extracts the `bytes` of an archive to the `out_dir`. The archive format (gzip, xz, bzip2 or
+/// zstd compressed tar, or zip) is detected from its magic bytes, so callers do not need to know
+/// the format up front.
 pub async fn extract(bytes: &Bytes, out_dir: &Path) -> Result<()> {
-    let input = BufReader::new(Cursor::new(bytes));
-    let decoder = GzDecoder::new(input);
+    let input = || BufReader::new(Cursor::new(bytes));
+
+    match ArchiveFormat::detect(bytes)? {
+        ArchiveFormat::TarGz => extract_tar(GzDecoder::new(input()), out_dir),
+        ArchiveFormat::TarXz => extract_tar(XzDecoder::new(input()), out_dir),
+        ArchiveFormat::TarBz2 => extract_tar(BzDecoder::new(input()), out_dir),
+        ArchiveFormat::TarZst => extract_tar(ZstdDecoder::new(input())?, out_dir),
+        ArchiveFormat::Zip => extract_zip(bytes, out_dir),
+    }
+}
+
+/// Extracts a tar `archive`, stripping the leading path component (e.g. the `postgresql-16.1.0`
+/// directory produced by the upstream release) and preserving Unix permissions.
+fn extract_tar<R: Read>(decoder: R, out_dir: &Path) -> Result<()> {
     let mut archive = Archive::new(decoder);
 
     for file in archive.entries()? {
@@ -233,6 +336,45 @@ pub async fn extract(bytes: &Bytes, out_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Extracts a zip `bytes` archive, preserving Unix permissions where the archive records them.
+#[allow(clippy::case_sensitive_file_extension_comparisons)]
+fn extract_zip(bytes: &Bytes, out_dir: &Path) -> Result<()> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))
+        .map_err(|error| Unexpected(format!("Failed to read zip archive: {error}")))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|error| Unexpected(format!("Failed to read zip entry: {error}")))?;
+        let Some(relative_path) = entry.enclosed_name() else {
+            continue;
+        };
+        let file_name = out_dir.to_path_buf().join(relative_path);
+
+        if entry.is_dir() {
+            create_dir_all(&file_name)?;
+            continue;
+        }
+
+        if let Some(parent) = file_name.parent() {
+            create_dir_all(parent)?;
+        }
+
+        let mut output_file = File::create(&file_name)?;
+        copy(&mut entry, &mut output_file)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Some(mode) = entry.unix_mode() {
+                output_file.set_permissions(std::fs::Permissions::from_mode(mode))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,4 +423,31 @@ mod tests {
         assert!(result.is_err());
         Ok(())
     }
+
+    #[test]
+    fn test_archive_format_detect() -> Result<()> {
+        assert_eq!(ArchiveFormat::TarGz, ArchiveFormat::detect(&[0x1F, 0x8B])?);
+        assert_eq!(
+            ArchiveFormat::TarXz,
+            ArchiveFormat::detect(&[0xFD, 0x37, 0x7A, 0x58, 0x5A, 0x00])?
+        );
+        assert_eq!(
+            ArchiveFormat::TarBz2,
+            ArchiveFormat::detect(&[0x42, 0x5A, 0x68])?
+        );
+        assert_eq!(
+            ArchiveFormat::TarZst,
+            ArchiveFormat::detect(&[0x28, 0xB5, 0x2F, 0xFD])?
+        );
+        assert_eq!(
+            ArchiveFormat::Zip,
+            ArchiveFormat::detect(&[0x50, 0x4B, 0x03, 0x04])?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_archive_format_detect_unrecognized() {
+        assert!(ArchiveFormat::detect(&[0x00, 0x00, 0x00, 0x00]).is_err());
+    }
 }
@@ -64,4 +64,22 @@ pub fn extract(bytes: &Bytes, out_dir: &Path) -> crate::Result<()> {
     RUNTIME
         .handle()
         .block_on(async move { crate::extract(bytes, out_dir).await })
-}
\ No newline at end of file
+}
+
+/// Removes all cached archives.
+///
+/// # Errors
+///
+/// Returns an error if the cache directory exists but cannot be removed.
+pub fn clear_cache() -> crate::Result<()> {
+    crate::clear_cache()
+}
+
+/// Removes the cached archive for the given [version](Version) and `target`.
+///
+/// # Errors
+///
+/// Returns an error if a cached file exists but cannot be removed.
+pub fn clear_cache_for<S: AsRef<str>>(version: &Version, target: S) -> crate::Result<()> {
+    crate::clear_cache_for(version, target)
+}
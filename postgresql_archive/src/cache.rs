@@ -0,0 +1,242 @@
+//! On-disk cache for downloaded archives.
+//!
+//! Archives are stored under `<cache_dir>/postgresql-archive/<releases-host>/<version>-<target>.tar.gz`,
+//! alongside the hash that was recorded when the archive was cached. Before a network download,
+//! callers check the cache for a file whose SHA-256 matches the hash published by the releases
+//! host; a corrupt or stale cached file is transparently discarded so the caller falls back to a
+//! fresh download.
+use crate::error::Result;
+use crate::version::Version;
+use bytes::Bytes;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+
+/// Environment variable that overrides the default cache directory.
+const CACHE_DIR_ENV: &str = "POSTGRESQL_ARCHIVE_CACHE_DIR";
+/// Sub-directory of the cache root that archives are stored under.
+const CACHE_SUBDIR: &str = "postgresql-archive";
+
+/// Returns the root directory used to cache downloaded archives. Defaults to the
+/// `postgresql-archive` directory under the platform cache directory (e.g. `~/.cache` on Linux),
+/// but can be overridden by setting the `POSTGRESQL_ARCHIVE_CACHE_DIR` ([`CACHE_DIR_ENV`])
+/// environment variable, whose value is then used as the root itself.
+fn cache_root() -> PathBuf {
+    if let Ok(dir) = std::env::var(CACHE_DIR_ENV) {
+        return PathBuf::from(dir);
+    }
+
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(CACHE_SUBDIR)
+}
+
+/// Returns the path of the cached archive for the given `releases_host`, `version` and `target`.
+fn cached_archive_path(releases_host: &str, version: &Version, target: &str) -> PathBuf {
+    cache_root()
+        .join(releases_host)
+        .join(format!("{version}-{target}.tar.gz"))
+}
+
+/// Returns the lowercase hex-encoded SHA-256 digest of `bytes`.
+fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Returns the cached archive for `releases_host`, `version` and `target` if it exists and its
+/// SHA-256 matches `hash`. A cached file that exists but does not match `hash` is treated as
+/// corrupt and removed so that it does not shadow a subsequent download.
+pub(crate) fn get(
+    releases_host: &str,
+    version: &Version,
+    target: &str,
+    hash: &str,
+) -> Option<Bytes> {
+    let path = cached_archive_path(releases_host, version, target);
+    let bytes = fs::read(&path).ok()?;
+
+    if sha256_hex(&bytes).eq_ignore_ascii_case(hash) {
+        Some(Bytes::from(bytes))
+    } else {
+        let _ = fs::remove_file(&path);
+        None
+    }
+}
+
+/// Stores `bytes` in the cache for `releases_host`, `version` and `target`. Caching is a
+/// best-effort optimization, so callers should not treat a failure here as fatal.
+pub(crate) fn put(
+    releases_host: &str,
+    version: &Version,
+    target: &str,
+    bytes: &Bytes,
+) -> Result<()> {
+    let path = cached_archive_path(releases_host, version, target);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Removes all cached archives.
+///
+/// # Errors
+/// Returns an error if the cache directory exists but cannot be removed.
+pub fn clear_cache() -> Result<()> {
+    let root = cache_root();
+    if root.exists() {
+        fs::remove_dir_all(root)?;
+    }
+    Ok(())
+}
+
+/// Removes the cached archive for the given `version` and `target`, across all releases hosts.
+///
+/// # Errors
+/// Returns an error if a cached file exists but cannot be removed.
+pub fn clear_cache_for(version: &Version, target: &str) -> Result<()> {
+    let root = cache_root();
+    if !root.exists() {
+        return Ok(());
+    }
+
+    let file_name = format!("{version}-{target}.tar.gz");
+    for entry in fs::read_dir(&root)? {
+        let host_dir = entry?.path();
+        if !host_dir.is_dir() {
+            continue;
+        }
+
+        let path = host_dir.join(&file_name);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Serializes tests that set [`CACHE_DIR_ENV`], since the environment is process-global.
+    static CACHE_DIR_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Points [`CACHE_DIR_ENV`] at a fresh temporary directory for the duration of `test`,
+    /// holding [`CACHE_DIR_LOCK`] so concurrently-running tests don't observe each other's
+    /// cache directory.
+    fn with_cache_dir(test: impl FnOnce()) {
+        let _guard = CACHE_DIR_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        std::env::set_var(CACHE_DIR_ENV, dir.path());
+        test();
+        std::env::remove_var(CACHE_DIR_ENV);
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        let hash = sha256_hex(b"");
+        assert_eq!(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            hash
+        );
+    }
+
+    #[test]
+    fn test_get_cache_miss() {
+        with_cache_dir(|| {
+            let version = Version::new(16, Some(1), Some(0));
+            assert!(get("github.com", &version, "does-not-exist-target", "0").is_none());
+        });
+    }
+
+    #[test]
+    fn test_put_then_get_is_a_cache_hit() {
+        with_cache_dir(|| {
+            let version = Version::new(16, Some(1), Some(0));
+            let bytes = Bytes::from_static(b"archive contents");
+            let hash = sha256_hex(&bytes);
+
+            put("github.com", &version, "x86_64-unknown-linux-gnu", &bytes)
+                .expect("put should succeed");
+
+            let cached = get("github.com", &version, "x86_64-unknown-linux-gnu", &hash)
+                .expect("cache hit expected");
+            assert_eq!(bytes, cached);
+        });
+    }
+
+    #[test]
+    fn test_get_evicts_corrupt_cached_file() {
+        with_cache_dir(|| {
+            let version = Version::new(16, Some(1), Some(0));
+            let bytes = Bytes::from_static(b"archive contents");
+
+            put("github.com", &version, "x86_64-unknown-linux-gnu", &bytes)
+                .expect("put should succeed");
+            let path = cached_archive_path("github.com", &version, "x86_64-unknown-linux-gnu");
+            assert!(path.exists());
+
+            // The hash published by the releases host no longer matches what's on disk, e.g.
+            // because the cached file was truncated or corrupted.
+            let wrong_hash = sha256_hex(b"not the archive that was cached");
+            assert!(get(
+                "github.com",
+                &version,
+                "x86_64-unknown-linux-gnu",
+                &wrong_hash
+            )
+            .is_none());
+            assert!(!path.exists());
+        });
+    }
+
+    #[test]
+    fn test_clear_cache_removes_all_cached_archives() {
+        with_cache_dir(|| {
+            let version = Version::new(16, Some(1), Some(0));
+            let bytes = Bytes::from_static(b"archive contents");
+            put("github.com", &version, "x86_64-unknown-linux-gnu", &bytes)
+                .expect("put should succeed");
+
+            clear_cache().expect("clear_cache should succeed");
+
+            assert!(!cache_root().exists());
+        });
+    }
+
+    #[test]
+    fn test_clear_cache_for_removes_only_matching_version_and_target() {
+        with_cache_dir(|| {
+            let version = Version::new(16, Some(1), Some(0));
+            let other_version = Version::new(17, Some(0), Some(0));
+            let bytes = Bytes::from_static(b"archive contents");
+
+            put("github.com", &version, "x86_64-unknown-linux-gnu", &bytes)
+                .expect("put should succeed");
+            put(
+                "github.com",
+                &other_version,
+                "x86_64-unknown-linux-gnu",
+                &bytes,
+            )
+            .expect("put should succeed");
+
+            clear_cache_for(&version, "x86_64-unknown-linux-gnu")
+                .expect("clear_cache_for should succeed");
+
+            assert!(
+                !cached_archive_path("github.com", &version, "x86_64-unknown-linux-gnu").exists()
+            );
+            assert!(
+                cached_archive_path("github.com", &other_version, "x86_64-unknown-linux-gnu")
+                    .exists()
+            );
+        });
+    }
+}
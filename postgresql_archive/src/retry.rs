@@ -0,0 +1,176 @@
+//! Retry support for transient failures when calling the GitHub API.
+use crate::error::Result;
+use rand::Rng;
+use reqwest::header::HeaderMap;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use std::time::Duration;
+
+lazy_static! {
+    static ref RETRY_CONFIG: std::sync::RwLock<RetryConfig> =
+        std::sync::RwLock::new(RetryConfig::default());
+}
+
+/// Configuration for the retry/backoff behavior used when calling the GitHub API. Retries are
+/// attempted on rate limiting (`429`, or `403` with `X-RateLimit-Remaining: 0`), `5xx` responses
+/// and connection/timeout errors.
+#[derive(Clone, Debug)]
+pub struct RetryConfig {
+    /// Maximum number of attempts, including the first, before giving up.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on each subsequent attempt.
+    pub base_delay: Duration,
+    /// Upper bound on a `Retry-After` delay and on the exponential backoff delay, regardless of
+    /// attempt count. Jitter of up to a quarter of the capped backoff delay is added on top.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// A configuration that disables retries; the request is attempted exactly once.
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+}
+
+/// Overrides the retry/backoff behavior used for GitHub API requests made by this crate. Pass
+/// [`RetryConfig::disabled`] to turn off retries entirely.
+pub fn set_retry_config(config: RetryConfig) {
+    if let Ok(mut guard) = RETRY_CONFIG.write() {
+        *guard = config;
+    }
+}
+
+/// Returns the currently configured [`RetryConfig`].
+fn retry_config() -> RetryConfig {
+    RETRY_CONFIG
+        .read()
+        .map(|guard| guard.clone())
+        .unwrap_or_default()
+}
+
+/// Returns `true` if `status`/`headers` indicate a condition worth retrying.
+fn is_retryable(status: StatusCode, headers: &HeaderMap) -> bool {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return true;
+    }
+
+    if status == StatusCode::FORBIDDEN {
+        let remaining = headers
+            .get("x-ratelimit-remaining")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        return remaining == Some(0);
+    }
+
+    status.is_server_error()
+}
+
+/// Returns the delay requested by a `Retry-After` header, if present.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+/// Computes the delay to wait before `attempt` (1-based), honoring `retry_after` when present and
+/// otherwise using exponential backoff with jitter.
+fn backoff_delay(config: &RetryConfig, attempt: u32, retry_after: Option<Duration>) -> Duration {
+    if let Some(delay) = retry_after {
+        return delay.min(config.max_delay);
+    }
+
+    let exponent = attempt.saturating_sub(1).min(16);
+    let delay = config.base_delay.saturating_mul(1u32 << exponent);
+    let delay = delay.min(config.max_delay);
+    let jitter_bound = u64::try_from(delay.as_millis() / 4)
+        .unwrap_or(u64::MAX)
+        .max(1);
+    let jitter = rand::thread_rng().gen_range(0..=jitter_bound);
+
+    delay + Duration::from_millis(jitter)
+}
+
+/// Sends `request`, retrying according to the globally configured [`RetryConfig`] (see
+/// [`set_retry_config`]) on rate limiting, server errors and connection failures.
+pub(crate) async fn send(request: RequestBuilder) -> Result<Response> {
+    let config = retry_config();
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        let Some(attempt_request) = request.try_clone() else {
+            return request.send().await.map_err(Into::into);
+        };
+
+        match attempt_request.send().await {
+            Ok(response) => {
+                let status = response.status();
+                if attempt >= config.max_attempts || !is_retryable(status, response.headers()) {
+                    return Ok(response);
+                }
+
+                let delay = backoff_delay(&config, attempt, retry_after(response.headers()));
+                tokio::time::sleep(delay).await;
+            }
+            Err(error) => {
+                if attempt >= config.max_attempts || !(error.is_connect() || error.is_timeout()) {
+                    return Err(error.into());
+                }
+
+                let delay = backoff_delay(&config, attempt, None);
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_config_default() {
+        let config = RetryConfig::default();
+        assert_eq!(5, config.max_attempts);
+    }
+
+    #[test]
+    fn test_retry_config_disabled() {
+        let config = RetryConfig::disabled();
+        assert_eq!(1, config.max_attempts);
+    }
+
+    #[test]
+    fn test_backoff_delay_honors_retry_after() {
+        let config = RetryConfig::default();
+        let delay = backoff_delay(&config, 1, Some(Duration::from_secs(1)));
+        assert_eq!(Duration::from_secs(1), delay);
+    }
+
+    #[test]
+    fn test_backoff_delay_caps_at_max_delay() {
+        let config = RetryConfig::default();
+        let delay = backoff_delay(&config, 100, None);
+        assert!(delay >= config.max_delay);
+        assert!(
+            delay
+                <= config.max_delay
+                    + Duration::from_millis(config.max_delay.as_millis() as u64 / 4 + 1)
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use crate::model::AvailableExtension;
+use crate::repository::manifest;
+use crate::repository::{registry, Repository};
+use crate::Result;
+use async_trait::async_trait;
+use regex::Regex;
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+use std::fmt::Debug;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Number of releases requested per page when paginating through the GitHub releases API.
+const RELEASES_PER_PAGE: u32 = 100;
+
+/// Placeholders that may appear in an asset naming pattern. Each is substituted with a named
+/// capture group when matching release assets; the asset is then downloaded from its own
+/// `browser_download_url`, not from a URL built from the pattern.
+const PLACEHOLDERS: [&str; 4] = ["postgresql_version", "name", "version", "target"];
+
+/// A release, as returned by the GitHub API.
+#[derive(Debug, Deserialize)]
+struct Release {
+    assets: Vec<Asset>,
+}
+
+/// A release asset, as returned by the GitHub API.
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Generic extension repository backed by a GitHub releases feed. Unlike
+/// [`PortalCorp`](crate::repository::portal_corp::PortalCorp), which only ever serves
+/// `pgvector_compiled`, this repository can be pointed at any `owner/repo` whose releases follow
+/// a configurable asset naming pattern.
+#[derive(Debug)]
+pub struct GitHubReleaseRepository {
+    name: String,
+    owner: String,
+    repo: String,
+    asset_pattern: String,
+}
+
+impl GitHubReleaseRepository {
+    /// Creates a new GitHub release repository named `name` for `owner/repo`, matching assets
+    /// against `asset_pattern`. The pattern may contain the `{postgresql_version}`, `{name}`,
+    /// `{version}` and `{target}` placeholders, e.g.
+    /// `"{name}-{version}-postgresql{postgresql_version}-{target}.zip"`.
+    ///
+    /// # Errors
+    /// * If the repository cannot be created.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<S: Into<String>>(
+        name: S,
+        owner: S,
+        repo: S,
+        asset_pattern: S,
+    ) -> Result<Box<dyn Repository>> {
+        Ok(Box::new(Self {
+            name: name.into(),
+            owner: owner.into(),
+            repo: repo.into(),
+            asset_pattern: asset_pattern.into(),
+        }))
+    }
+
+    /// Registers this repository in the [`registry`](crate::repository::registry) under its own
+    /// name, so it can be looked up without threading it through the call stack.
+    ///
+    /// # Errors
+    /// * If the repository cannot be registered.
+    pub fn initialize(self) -> Result<()> {
+        registry::register(Arc::new(self))
+    }
+
+    /// Compiles [`Self::asset_pattern`] to a regular expression whose named capture groups
+    /// correspond to the placeholders present in the pattern. Capture groups are non-greedy so
+    /// that adjacent placeholders separated only by a literal (e.g. `{name}-{version}`) don't
+    /// swallow the following field when the separator also appears inside it, as it commonly does
+    /// in target triples like `x86_64-unknown-linux-gnu`.
+    fn pattern_regex(&self) -> Result<Regex> {
+        let mut pattern = regex::escape(&self.asset_pattern);
+
+        for placeholder in PLACEHOLDERS {
+            let needle = regex::escape(&format!("{{{placeholder}}}"));
+            let capture_group = format!("(?P<{placeholder}>[^/]+?)");
+            pattern = pattern.replace(&needle, &capture_group);
+        }
+
+        let regex = Regex::new(&format!("^{pattern}$"))?;
+        Ok(regex)
+    }
+
+    /// Fetches all releases for [`owner/repo`](Self::owner), paginating until an empty page is
+    /// returned.
+    async fn releases(&self) -> Result<Vec<Release>> {
+        let client = reqwest::Client::new();
+        let mut releases = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let url = format!(
+                "https://api.github.com/repos/{owner}/{repo}/releases?page={page}&per_page={RELEASES_PER_PAGE}",
+                owner = self.owner,
+                repo = self.repo,
+            );
+            let response = client
+                .get(url)
+                .header(reqwest::header::USER_AGENT, env!("CARGO_PKG_NAME"))
+                .send()
+                .await?
+                .error_for_status()?;
+            let mut page_releases = response.json::<Vec<Release>>().await?;
+
+            if page_releases.is_empty() {
+                break;
+            }
+
+            releases.append(&mut page_releases);
+            page += 1;
+        }
+
+        Ok(releases)
+    }
+
+    /// Finds the release asset whose name matches [`Self::asset_pattern`] for the given `name`
+    /// and `version_req`, along with the matched extension version, preferring the highest
+    /// matching version.
+    async fn find_asset(
+        &self,
+        postgresql_version: &str,
+        name: &str,
+        version_req: &VersionReq,
+    ) -> Result<(Version, Asset)> {
+        let regex = self.pattern_regex()?;
+        let mut result: Option<(Version, Asset)> = None;
+
+        for release in self.releases().await? {
+            for asset in release.assets {
+                let Some(captures) = regex.captures(&asset.name) else {
+                    continue;
+                };
+
+                if captures.name("name").map(|value| value.as_str()) != Some(name) {
+                    continue;
+                }
+
+                if let Some(expected_postgresql_version) = captures.name("postgresql_version") {
+                    if expected_postgresql_version.as_str() != postgresql_version {
+                        continue;
+                    }
+                }
+
+                let Some(version_match) = captures.name("version") else {
+                    continue;
+                };
+                let Ok(version) = Version::parse(version_match.as_str()) else {
+                    continue;
+                };
+
+                if !version_req.matches(&version) {
+                    continue;
+                }
+
+                let is_newer = result
+                    .as_ref()
+                    .map(|(current, _)| version > *current)
+                    .unwrap_or(true);
+                if is_newer {
+                    result = Some((version, asset));
+                }
+            }
+        }
+
+        result.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no release asset matching '{name}' {version_req} was found"),
+            )
+            .into()
+        })
+    }
+}
+
+#[async_trait]
+impl Repository for GitHubReleaseRepository {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn get_available_extensions(&self) -> Result<Vec<AvailableExtension>> {
+        let regex = self.pattern_regex()?;
+        let mut names = Vec::new();
+
+        for release in self.releases().await? {
+            for asset in release.assets {
+                let Some(captures) = regex.captures(&asset.name) else {
+                    continue;
+                };
+                let Some(name) = captures.name("name") else {
+                    continue;
+                };
+                let name = name.as_str().to_string();
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+
+        let description = format!(
+            "GitHub release extensions from {owner}/{repo}",
+            owner = self.owner,
+            repo = self.repo,
+        );
+        let extensions = names
+            .into_iter()
+            .map(|name| AvailableExtension::new(self.name(), name, description.clone()))
+            .collect();
+        Ok(extensions)
+    }
+
+    async fn get_archive(
+        &self,
+        postgresql_version: &str,
+        name: &str,
+        version: &VersionReq,
+    ) -> Result<(Version, Vec<u8>)> {
+        let (version, asset) = self.find_asset(postgresql_version, name, version).await?;
+        let client = reqwest::Client::new();
+        let archive = client
+            .get(asset.browser_download_url.clone())
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?
+            .to_vec();
+
+        manifest::verify_archive_hash(&asset.browser_download_url, &archive).await?;
+
+        Ok((version, archive))
+    }
+
+    async fn install(
+        &self,
+        name: &str,
+        library_dir: PathBuf,
+        extension_dir: PathBuf,
+        archive: &[u8],
+    ) -> Result<Vec<PathBuf>> {
+        manifest::install(name, library_dir, extension_dir, archive).await
+    }
+
+    async fn uninstall(&self, name: &str, extension_dir: PathBuf) -> Result<()> {
+        manifest::uninstall(name, extension_dir).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repository() -> GitHubReleaseRepository {
+        GitHubReleaseRepository {
+            name: "github-release".to_string(),
+            owner: "example".to_string(),
+            repo: "extensions".to_string(),
+            asset_pattern: "{name}-{version}-pg{postgresql_version}-{target}.zip".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!("github-release", repository().name());
+    }
+
+    #[test]
+    fn test_pattern_regex_matches() -> Result<()> {
+        let regex = repository().pattern_regex()?;
+        let captures = regex
+            .captures("pgvector-0.7.0-pg16-x86_64-unknown-linux-gnu.zip")
+            .expect("pattern should match");
+        assert_eq!("pgvector", &captures["name"]);
+        assert_eq!("0.7.0", &captures["version"]);
+        assert_eq!("16", &captures["postgresql_version"]);
+        assert_eq!("x86_64-unknown-linux-gnu", &captures["target"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pattern_regex_no_match() -> Result<()> {
+        let regex = repository().pattern_regex()?;
+        assert!(regex.captures("not-a-matching-asset-name.tar.gz").is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_install_and_uninstall_round_trip() -> Result<()> {
+        let repository = repository();
+        let library_dir = tempfile::tempdir()?;
+        let extension_dir = tempfile::tempdir()?;
+        let archive = manifest::test_support::build_tar_gz_fixture(&[
+            ("pgvector.so", b"library"),
+            ("pgvector.control", b"control"),
+            ("pgvector--0.7.0.sql", b"sql"),
+        ])?;
+
+        let installed = repository
+            .install(
+                "pgvector",
+                library_dir.path().to_path_buf(),
+                extension_dir.path().to_path_buf(),
+                &archive,
+            )
+            .await?;
+        assert_eq!(3, installed.len());
+        for path in &installed {
+            assert!(path.exists());
+        }
+
+        let manifest_file = manifest::manifest_path(extension_dir.path(), "pgvector");
+        assert!(manifest_file.exists());
+
+        repository
+            .uninstall("pgvector", extension_dir.path().to_path_buf())
+            .await?;
+        for path in &installed {
+            assert!(!path.exists());
+        }
+        assert!(!manifest_file.exists());
+
+        Ok(())
+    }
+}
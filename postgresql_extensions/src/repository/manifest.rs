@@ -0,0 +1,179 @@
+//! Install/uninstall infrastructure shared by [`Repository`](crate::repository::Repository)
+//! implementations that extract an archive onto disk: an install manifest (mirroring how docs.rs
+//! records per-build status) so an install can later be undone exactly, and the hash
+//! verification that every `.sha256`-publishing repository (`PortalCorp`,
+//! `GitHubReleaseRepository`) needs to run against a downloaded archive.
+use crate::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// A single file written by a `Repository::install`, recorded so `Repository::uninstall` can
+/// remove exactly what was installed.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ManifestEntry {
+    pub(crate) path: PathBuf,
+    pub(crate) size: u64,
+    pub(crate) sha256: String,
+}
+
+/// Manifest written to the extension directory on install, mirroring how docs.rs records
+/// per-build status.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct InstallManifest {
+    pub(crate) files: Vec<ManifestEntry>,
+}
+
+/// Returns the path of the install manifest for extension `name` in `extension_dir`.
+pub(crate) fn manifest_path(extension_dir: &Path, name: &str) -> PathBuf {
+    extension_dir.join(format!("{name}.manifest.json"))
+}
+
+/// Returns the lowercase hex-encoded SHA-256 digest of `bytes`.
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Fetches the `.sha256` sidecar published alongside `archive_url` and verifies that it matches
+/// `archive`.
+pub(crate) async fn verify_archive_hash(archive_url: &str, archive: &[u8]) -> Result<()> {
+    let hash_url = format!("{archive_url}.sha256");
+    let text = reqwest::get(hash_url)
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let expected_hash = Regex::new(r"[0-9a-f]{64}")?
+        .find(&text)
+        .map(|hash| hash.as_str().to_string())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("sha256 hash not found for archive at '{archive_url}'"),
+            )
+        })?;
+
+    if !sha256_hex(archive).eq_ignore_ascii_case(&expected_hash) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("hash mismatch for archive at '{archive_url}'"),
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Extracts `archive` and copies its `.so`/`.dylib` files into `library_dir` and its
+/// `.control`/`.sql` files into `extension_dir`, recording what was written in an
+/// [`InstallManifest`] at [`manifest_path`]. Returns the paths of the installed files.
+#[allow(clippy::case_sensitive_file_extension_comparisons)]
+pub(crate) async fn install(
+    name: &str,
+    library_dir: PathBuf,
+    extension_dir: PathBuf,
+    archive: &[u8],
+) -> Result<Vec<PathBuf>> {
+    let out_dir = tempfile::tempdir()?;
+    let bytes = bytes::Bytes::copy_from_slice(archive);
+    postgresql_archive::extract(&bytes, out_dir.path()).await?;
+
+    let mut files = Vec::new();
+    let mut manifest = InstallManifest::default();
+
+    for entry in walk_files(out_dir.path())? {
+        let Some(file_name) = entry
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+        else {
+            continue;
+        };
+
+        let destination = if file_name.ends_with(".so") || file_name.ends_with(".dylib") {
+            library_dir.join(&file_name)
+        } else if file_name.ends_with(".control") || file_name.ends_with(".sql") {
+            extension_dir.join(&file_name)
+        } else {
+            continue;
+        };
+
+        let contents = fs::read(&entry)?;
+        fs::write(&destination, &contents)?;
+
+        manifest.files.push(ManifestEntry {
+            path: destination.clone(),
+            size: contents.len() as u64,
+            sha256: sha256_hex(&contents),
+        });
+        files.push(destination);
+    }
+
+    let manifest_json = serde_json::to_string_pretty(&manifest)?;
+    fs::write(manifest_path(&extension_dir, name), manifest_json)?;
+
+    Ok(files)
+}
+
+/// Removes extension `name`, previously installed into `extension_dir` by [`install`], using the
+/// manifest written at install time.
+pub(crate) async fn uninstall(name: &str, extension_dir: PathBuf) -> Result<()> {
+    let manifest_file = manifest_path(&extension_dir, name);
+    let manifest_json = fs::read_to_string(&manifest_file)?;
+    let manifest: InstallManifest = serde_json::from_str(&manifest_json)?;
+
+    for entry in &manifest.files {
+        if entry.path.exists() {
+            fs::remove_file(&entry.path)?;
+        }
+    }
+
+    fs::remove_file(&manifest_file)?;
+    Ok(())
+}
+
+/// Recursively collects the paths of all regular files under `dir`.
+fn walk_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(walk_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Test-only fixtures shared by the `install`/`uninstall` round-trip tests in
+/// [`github_release`](crate::repository::github_release) and
+/// [`portal_corp`](crate::repository::portal_corp).
+#[cfg(test)]
+pub(crate) mod test_support {
+    use std::io;
+
+    /// Builds a gzip-compressed tar archive containing `entries` as `(path, contents)` pairs,
+    /// nested under a single top-level directory, which `postgresql_archive::extract` strips.
+    pub(crate) fn build_tar_gz_fixture(entries: &[(&str, &[u8])]) -> io::Result<Vec<u8>> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, contents) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, format!("extension/{path}"), *contents)?;
+        }
+        let tar_bytes = builder.into_inner()?;
+
+        let mut archive = Vec::new();
+        let mut encoder =
+            flate2::write::GzEncoder::new(&mut archive, flate2::Compression::default());
+        io::Write::write_all(&mut encoder, &tar_bytes)?;
+        encoder.finish()?;
+        Ok(archive)
+    }
+}
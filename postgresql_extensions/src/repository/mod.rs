@@ -0,0 +1,60 @@
+//! Extension repository abstraction.
+//!
+//! A [`Repository`] knows how to list the extensions it offers, fetch an extension archive for a
+//! given PostgreSQL version, and install/uninstall that archive on disk. Concrete repositories
+//! ([`portal_corp::PortalCorp`], [`github_release::GitHubReleaseRepository`]) are looked up by
+//! name through the [`registry`].
+use crate::model::AvailableExtension;
+use crate::Result;
+use async_trait::async_trait;
+use semver::{Version, VersionReq};
+use std::fmt::Debug;
+use std::path::PathBuf;
+
+pub mod github_release;
+mod manifest;
+pub mod portal_corp;
+pub mod registry;
+
+/// A repository of precompiled PostgreSQL extensions.
+#[async_trait]
+pub trait Repository: Debug + Send + Sync {
+    /// Returns the name of the repository.
+    fn name(&self) -> &str;
+
+    /// Returns the extensions available from this repository.
+    ///
+    /// # Errors
+    /// * If the available extensions cannot be retrieved.
+    async fn get_available_extensions(&self) -> Result<Vec<AvailableExtension>>;
+
+    /// Gets the archive for extension `name` matching `version`, built for `postgresql_version`.
+    ///
+    /// # Errors
+    /// * If the archive cannot be retrieved.
+    async fn get_archive(
+        &self,
+        postgresql_version: &str,
+        name: &str,
+        version: &VersionReq,
+    ) -> Result<(Version, Vec<u8>)>;
+
+    /// Installs `archive` into `library_dir`/`extension_dir`, returning the paths of the files
+    /// that were installed.
+    ///
+    /// # Errors
+    /// * If the extension cannot be installed.
+    async fn install(
+        &self,
+        name: &str,
+        library_dir: PathBuf,
+        extension_dir: PathBuf,
+        archive: &[u8],
+    ) -> Result<Vec<PathBuf>>;
+
+    /// Removes extension `name`, previously installed into `extension_dir`.
+    ///
+    /// # Errors
+    /// * If the extension cannot be uninstalled.
+    async fn uninstall(&self, name: &str, extension_dir: PathBuf) -> Result<()>;
+}
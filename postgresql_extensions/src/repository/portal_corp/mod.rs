@@ -0,0 +1,11 @@
+mod repository;
+
+pub use repository::PortalCorp;
+
+/// Base URL of the PortalCorp extension repository.
+pub const URL: &str = "https://portal-corp.example.com/extensions";
+
+/// Matches an extension download URL against [`URL`].
+pub fn matcher(url: &str) -> postgresql_archive::Result<bool> {
+    Ok(url.starts_with(URL))
+}
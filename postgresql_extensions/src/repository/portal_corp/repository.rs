@@ -1,16 +1,15 @@
 use crate::model::AvailableExtension;
+use crate::repository::manifest;
 use crate::repository::portal_corp::URL;
-use crate::repository::{portal_corp, Repository};
+use crate::repository::{portal_corp, registry, Repository};
 use crate::Result;
 use async_trait::async_trait;
 use postgresql_archive::repository::github::repository::GitHub;
 use postgresql_archive::{get_archive, matcher};
 use semver::{Version, VersionReq};
 use std::fmt::Debug;
-use std::io::Cursor;
 use std::path::PathBuf;
-use std::{fs, io};
-use zip::ZipArchive;
+use std::sync::Arc;
 
 /// PortalCorp repository.
 #[derive(Debug)]
@@ -36,6 +35,7 @@ impl PortalCorp {
             |url| Ok(url.starts_with(URL)),
             Box::new(GitHub::new),
         )?;
+        registry::register(Arc::new(Self))?;
         Ok(())
     }
 }
@@ -62,47 +62,23 @@ impl Repository for PortalCorp {
         version: &VersionReq,
     ) -> Result<(Version, Vec<u8>)> {
         let url = format!("{URL}/{name}?postgresql_version={postgresql_version}");
-        let archive = get_archive(url.as_str(), version).await?;
-        Ok(archive)
+        let (version, archive) = get_archive(url.as_str(), version).await?;
+        manifest::verify_archive_hash(&url, &archive).await?;
+        Ok((version, archive))
     }
 
-    #[allow(clippy::case_sensitive_file_extension_comparisons)]
     async fn install(
         &self,
-        _name: &str,
+        name: &str,
         library_dir: PathBuf,
         extension_dir: PathBuf,
         archive: &[u8],
     ) -> Result<Vec<PathBuf>> {
-        let reader = Cursor::new(archive);
-        let mut archive = ZipArchive::new(reader)
-            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Zip error"))?;
-        let mut files = Vec::new();
-
-        for i in 0..archive.len() {
-            let mut file = archive
-                .by_index(i)
-                .map_err(|_| io::Error::new(io::ErrorKind::Other, "Zip error"))?;
-            let file_path = PathBuf::from(file.name());
-            let file_path = PathBuf::from(file_path.file_name().unwrap_or_default());
-            let file_name = file_path.to_string_lossy();
-
-            if file_name.ends_with(".dylib") || file_name.ends_with(".so") {
-                let mut out = Vec::new();
-                io::copy(&mut file, &mut out)?;
-                let path = PathBuf::from(&library_dir).join(file_path);
-                fs::write(&path, out)?;
-                files.push(path);
-            } else if file_name.ends_with(".control") || file_name.ends_with(".sql") {
-                let mut out = Vec::new();
-                io::copy(&mut file, &mut out)?;
-                let path = PathBuf::from(&extension_dir).join(file_path);
-                fs::write(&path, out)?;
-                files.push(path);
-            }
-        }
+        manifest::install(name, library_dir, extension_dir, archive).await
+    }
 
-        Ok(files)
+    async fn uninstall(&self, name: &str, extension_dir: PathBuf) -> Result<()> {
+        manifest::uninstall(name, extension_dir).await
     }
 }
 
@@ -130,4 +106,41 @@ mod tests {
         );
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_install_and_uninstall_round_trip() -> Result<()> {
+        let repository = PortalCorp;
+        let library_dir = tempfile::tempdir()?;
+        let extension_dir = tempfile::tempdir()?;
+        let archive = manifest::test_support::build_tar_gz_fixture(&[
+            ("pgvector.so", b"library"),
+            ("pgvector.control", b"control"),
+        ])?;
+
+        let installed = repository
+            .install(
+                "pgvector",
+                library_dir.path().to_path_buf(),
+                extension_dir.path().to_path_buf(),
+                &archive,
+            )
+            .await?;
+        assert_eq!(2, installed.len());
+        for path in &installed {
+            assert!(path.exists());
+        }
+
+        let manifest_file = manifest::manifest_path(extension_dir.path(), "pgvector");
+        assert!(manifest_file.exists());
+
+        repository
+            .uninstall("pgvector", extension_dir.path().to_path_buf())
+            .await?;
+        for path in &installed {
+            assert!(!path.exists());
+        }
+        assert!(!manifest_file.exists());
+
+        Ok(())
+    }
 }
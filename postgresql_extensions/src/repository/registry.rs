@@ -0,0 +1,67 @@
+//! Registry of configured [`Repository`] instances, keyed by name, so a repository configured at
+//! startup (e.g. a user-supplied
+//! [`GitHubReleaseRepository`](crate::repository::github_release::GitHubReleaseRepository))
+//! can be looked up without threading it through the call stack.
+use crate::repository::Repository;
+use crate::Result;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+lazy_static! {
+    static ref REGISTRY: RwLock<HashMap<String, Arc<dyn Repository>>> = RwLock::new(HashMap::new());
+}
+
+/// Registers `repository` under its own [`Repository::name`], replacing any repository
+/// previously registered under the same name.
+pub fn register(repository: Arc<dyn Repository>) -> Result<()> {
+    if let Ok(mut registry) = REGISTRY.write() {
+        registry.insert(repository.name().to_string(), repository);
+    }
+    Ok(())
+}
+
+/// Returns the repository registered under `name`, if any.
+pub fn get(name: &str) -> Option<Arc<dyn Repository>> {
+    REGISTRY.read().ok()?.get(name).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::manifest::test_support::build_tar_gz_fixture;
+    use crate::repository::portal_corp::PortalCorp;
+
+    /// Exercises the full path a configured repository is expected to take: it registers
+    /// itself under its own name (as `PortalCorp::initialize` does), is resolved back out of
+    /// the registry by that name, and can then serve an install/uninstall round trip.
+    #[tokio::test]
+    async fn test_register_then_get_resolves_and_installs() -> Result<()> {
+        register(Arc::new(PortalCorp))?;
+
+        let repository = get("portal-corp").expect("repository should be registered");
+        assert_eq!("portal-corp", repository.name());
+
+        let library_dir = tempfile::tempdir()?;
+        let extension_dir = tempfile::tempdir()?;
+        let archive = build_tar_gz_fixture(&[
+            ("pgvector.so", b"library"),
+            ("pgvector.control", b"control"),
+        ])?;
+
+        let installed = repository
+            .install(
+                "pgvector",
+                library_dir.path().to_path_buf(),
+                extension_dir.path().to_path_buf(),
+                &archive,
+            )
+            .await?;
+        assert_eq!(2, installed.len());
+
+        repository
+            .uninstall("pgvector", extension_dir.path().to_path_buf())
+            .await?;
+
+        Ok(())
+    }
+}